@@ -4,8 +4,34 @@
 //! alphanumeric LCD using the
 //! [HD44780](https://crates.io/crates/hd44780-driver) driver.
 //!
-//! It drives the LCD by pushing data out of six GPIO pins. It may need to be
-//! adapted to your particular board layout and/or pin assignment.
+//! Instead of bit-banging six GPIOs from the CPU, the 4-bit data/enable bus
+//! is driven by a PIO state machine through [`hal::pio::ParallelOut`]. The
+//! state machine owns the enable-strobe timing, so the CPU only has to hand
+//! over nibbles and can otherwise sit idle. `hd44780-driver` only knows how
+//! to bit-bang individual `OutputPin`s, so [`ParallelOutBus`] below adapts
+//! each of its per-line writes into a single PIO [`write_nibble`] call on
+//! the falling edge of the enable line - the same edge real HD44780
+//! hardware latches on.
+//!
+//! [`write_nibble`]: hal::pio::ParallelOut::write_nibble
+//!
+//! `hd44780-driver` itself is fully blocking (it targets `embedded-hal`
+//! 0.2's `DelayUs`/`DelayMs`, which [`hal::Timer`] implements directly), so
+//! the LCD writes below are all synchronous. To show off the other half of
+//! this example, the pause between writing the two lines uses an async
+//! delay backed by a TIMER alarm instead ([`hal::Timer::alarm0_async`]),
+//! so the core actually sleeps (`wfe()`) rather than spinning.
+//!
+//! It may need to be adapted to your particular board layout and/or pin
+//! assignment.
+//!
+//! Note: `hal::gpio::PinGroup` also exists for bundling a fixed set of pins
+//! (e.g. `pins.gpio18..pins.gpio21`) into a single typed handle, built by
+//! chaining `PinGroup::new().add_pin(..)` once per pin, with atomic
+//! `read()`/`set(state)`/`set_u32(mask)`/`set_bits(mask)`/`write(value)`/`toggle()`
+//! operations. It's the right tool if you need to drive a parallel bus
+//! straight from the CPU; this example doesn't need it since the
+//! data/enable bus above is owned by the PIO state machine instead.
 //!
 //! See the `Cargo.toml` file for Copyright and license details.
 
@@ -16,12 +42,23 @@
 // be linked)
 use panic_halt as _;
 
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+use core::future::Future;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal_async::delay::DelayNs;
+
 // Alias for our HAL crate
 use rp235x_hal as hal;
 
 // Our LCD driver
 use hd44780_driver as hd44780;
 
+use hal::pac::interrupt;
+use hal::pio::{PIOExt, ParallelOut, ParallelOutConfig, StateMachineIndex};
+
 /// Tell the Boot ROM about our application
 #[link_section = ".start_block"]
 #[used]
@@ -31,6 +68,112 @@ pub static IMAGE_DEF: hal::block::ImageDef = hal::block::ImageDef::secure_exe();
 /// Adjust if your board has a different frequency
 const XTAL_FREQ_HZ: u32 = 12_000_000u32;
 
+/// Runs `future` to completion on the current core, sleeping with `wfe()`
+/// between polls and relying on interrupt handlers to `SEV` us back awake.
+///
+/// This is not a general-purpose executor - it only ever has one future in
+/// flight - but it is enough to let the demo delay below sleep through its
+/// wait instead of busy-waiting.
+fn block_on<F: Future>(mut future: F) -> F::Output {
+    // Safety: the waker never touches its data; wake just asks the core to
+    // stop waiting for an event, which is always sound to do spuriously.
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(core::ptr::null(), &VTABLE),
+        |_| cortex_m::asm::sev(),
+        |_| cortex_m::asm::sev(),
+        |_| {},
+    );
+    let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+
+    // Safety: `future` is never moved after this point.
+    let mut future = unsafe { core::pin::Pin::new_unchecked(&mut future) };
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => cortex_m::asm::wfe(),
+        }
+    }
+}
+
+/// Adapts a [`ParallelOut`] nibble bus to the individual `OutputPin`s that
+/// `hd44780-driver`'s 4-bit constructor bit-bangs.
+///
+/// `hd44780-driver` sets `d4..d7` then pulses `en` high and low around each
+/// nibble. This buffers the `d4..d7` writes and flushes them as a single
+/// [`ParallelOut::write_nibble`] call when `en` goes low, matching the edge
+/// real hardware latches on.
+struct ParallelOutBus<P: PIOExt, SM: StateMachineIndex> {
+    bus: RefCell<ParallelOut<P, SM, 4>>,
+    pending_nibble: Cell<u8>,
+}
+
+impl<P: PIOExt, SM: StateMachineIndex> ParallelOutBus<P, SM> {
+    fn new(bus: ParallelOut<P, SM, 4>) -> Self {
+        Self {
+            bus: RefCell::new(bus),
+            pending_nibble: Cell::new(0),
+        }
+    }
+
+    /// Returns the `OutputPin` standing in for data line `bit` (0 = d4, .. 3 = d7).
+    fn data_line(&self, bit: u8) -> DataLine<'_, P, SM> {
+        DataLine { shared: self, bit }
+    }
+
+    /// Returns the `OutputPin` standing in for the enable line.
+    fn enable_line(&self) -> EnableLine<'_, P, SM> {
+        EnableLine { shared: self }
+    }
+}
+
+struct DataLine<'a, P: PIOExt, SM: StateMachineIndex> {
+    shared: &'a ParallelOutBus<P, SM>,
+    bit: u8,
+}
+
+impl<P: PIOExt, SM: StateMachineIndex> OutputPin for DataLine<'_, P, SM> {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        let mask = 1 << self.bit;
+        self.shared
+            .pending_nibble
+            .set(self.shared.pending_nibble.get() & !mask);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        let mask = 1 << self.bit;
+        self.shared
+            .pending_nibble
+            .set(self.shared.pending_nibble.get() | mask);
+        Ok(())
+    }
+}
+
+struct EnableLine<'a, P: PIOExt, SM: StateMachineIndex> {
+    shared: &'a ParallelOutBus<P, SM>,
+}
+
+impl<P: PIOExt, SM: StateMachineIndex> OutputPin for EnableLine<'_, P, SM> {
+    type Error = Infallible;
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        // The rising edge just marks "the nibble on d4..d7 is now stable";
+        // the actual latch happens on the falling edge, below.
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.shared
+            .bus
+            .borrow_mut()
+            .write_nibble(self.shared.pending_nibble.get());
+        Ok(())
+    }
+}
+
 /// Entry point to our bare-metal application.
 ///
 /// The `#[hal::entry]` macro ensures the Cortex-M start-up code calls this function
@@ -40,6 +183,16 @@ const XTAL_FREQ_HZ: u32 = 12_000_000u32;
 /// to sleep.
 #[hal::entry]
 fn main() -> ! {
+    block_on(run());
+
+    // Do nothing - we're finished
+    loop {
+        hal::arch::wfi();
+    }
+}
+
+/// Configures the peripherals and writes the demo text to the LCD.
+async fn run() {
     // Grab our singleton objects
     let mut pac = hal::pac::Peripherals::take().unwrap();
 
@@ -58,9 +211,13 @@ fn main() -> ! {
     )
     .unwrap();
 
-    // The delay object lets us wait for specified amounts of time (in
-    // milliseconds)
-    let mut delay = hal::Timer::new_timer0(pac.TIMER0, &mut pac.RESETS, &clocks);
+    // The timer drives both the HD44780's blocking settling delays and, via
+    // its first alarm, the async delay used between the two lines below.
+    let mut timer = hal::Timer::new_timer0(pac.TIMER0, &mut pac.RESETS, &clocks);
+    let mut async_delay = timer.alarm0_async().unwrap();
+    unsafe {
+        hal::pac::NVIC::unmask(hal::pac::Interrupt::TIMER0_IRQ_0);
+    }
 
     // The single-cycle I/O block controls our GPIO pins
     let sio = hal::Sio::new(pac.SIO);
@@ -73,35 +230,65 @@ fn main() -> ! {
         &mut pac.RESETS,
     );
 
-    // Create the LCD driver from some GPIO pins
+    // Split off a PIO and one of its four state machines
+    let (mut pio, sm0, _, _, _) = pac.PIO0.split(&mut pac.RESETS);
+
+    // Reserve gpio18..gpio21 as the 4-bit data bus and gpio17 as the enable
+    // strobe. The state machine toggles enable itself once per nibble,
+    // timed in PIO clock cycles so it tracks the system clock rather than a
+    // fixed number of CPU instructions.
+    let bus_config = ParallelOutConfig::new()
+        .enable_pulse_cycles(20)
+        .setup_cycles(4)
+        .hold_cycles(4);
+    let bus = ParallelOut::new_4bit(
+        &mut pio,
+        sm0,
+        pins.gpio18.into_function(), // d4
+        pins.gpio19.into_function(), // d5
+        pins.gpio20.into_function(), // d6
+        pins.gpio21.into_function(), // d7
+        pins.gpio17.into_function(), // enable
+        bus_config,
+    );
+    let bus = ParallelOutBus::new(bus);
+
+    // Create the LCD driver. `hd44780-driver` bit-bangs RS/EN/D4..D7 as
+    // individual pins; RS is a plain GPIO, while EN/D4..D7 are backed by the
+    // PIO bus through `ParallelOutBus`.
     let mut lcd = hd44780::HD44780::new_4bit(
         pins.gpio16.into_push_pull_output(), // Register Select
-        pins.gpio17.into_push_pull_output(), // Enable
-        pins.gpio18.into_push_pull_output(), // d4
-        pins.gpio19.into_push_pull_output(), // d5
-        pins.gpio20.into_push_pull_output(), // d6
-        pins.gpio21.into_push_pull_output(), // d7
-        &mut delay,
+        bus.enable_line(),
+        bus.data_line(0), // d4
+        bus.data_line(1), // d5
+        bus.data_line(2), // d6
+        bus.data_line(3), // d7
+        &mut timer,
     )
     .unwrap();
 
     // Clear the screen
-    lcd.reset(&mut delay).unwrap();
-    lcd.clear(&mut delay).unwrap();
+    lcd.reset(&mut timer).unwrap();
+    lcd.clear(&mut timer).unwrap();
 
     // Write to the top line
-    lcd.write_str("rp-hal on", &mut delay).unwrap();
+    lcd.write_str("rp-hal on", &mut timer).unwrap();
+
+    // Pause between lines using the TIMER-alarm-backed async delay, so the
+    // core sleeps (`wfe()`) instead of spinning.
+    async_delay.delay_ms(500).await;
 
     // Move the cursor
-    lcd.set_cursor_pos(40, &mut delay).unwrap();
+    lcd.set_cursor_pos(40, &mut timer).unwrap();
 
     // Write more more text
-    lcd.write_str("HD44780!", &mut delay).unwrap();
+    lcd.write_str("HD44780!", &mut timer).unwrap();
+}
 
-    // Do nothing - we're finished
-    loop {
-        hal::arch::wfi();
-    }
+/// Wakes the pending `alarm0_async` delay, if any, and clears its IRQ.
+#[interrupt]
+fn TIMER0_IRQ_0() {
+    <hal::timer::Alarm0<hal::timer::CopyableTimer0> as hal::async_utils::AsyncPeripheral>::on_interrupt();
 }
 
 /// Program metadata for `picotool info`
@@ -0,0 +1,406 @@
+//! A PIO-driven parallel output bus with a CPU-free enable strobe.
+//!
+//! [`ParallelOut`] drives a group of data pins plus a single enable/strobe
+//! pin entirely from a PIO state machine: the core only ever hands over a
+//! nibble or byte through the Tx FIFO, and the state machine takes care of
+//! settling the data pins, pulsing the enable line and holding it, all
+//! timed in PIO clock cycles rather than CPU instructions. This is the
+//! pattern used by parallel LCDs (e.g. the HD44780, in either 4-bit or
+//! 8-bit mode), parallel EEPROMs/SRAMs, and similar enable-strobed buses.
+//!
+//! ```no_run
+//! use rp235x_hal as hal;
+//! use hal::pio::{ParallelOut, ParallelOutConfig, PIOExt};
+//!
+//! # fn foo(mut pac: hal::pac::Peripherals, pins: hal::gpio::Pins) {
+//! let (mut pio, sm0, _, _, _) = pac.PIO0.split(&mut pac.RESETS);
+//!
+//! let config = ParallelOutConfig::new()
+//!     .enable_pulse_cycles(20)
+//!     .setup_cycles(4)
+//!     .hold_cycles(4);
+//! let mut bus = ParallelOut::new_4bit(
+//!     &mut pio,
+//!     sm0,
+//!     pins.gpio18.into_function(), // d4
+//!     pins.gpio19.into_function(), // d5
+//!     pins.gpio20.into_function(), // d6
+//!     pins.gpio21.into_function(), // d7
+//!     pins.gpio17.into_function(), // enable
+//!     config,
+//! );
+//! bus.write_byte(0x42);
+//! # }
+//! ```
+
+use pio::{Assembler, OutDestination, SetDestination};
+
+use super::{
+    PIOExt, PinDir, Rx, StateMachine, StateMachineIndex, Tx, UninitStateMachine, ValidStateMachine,
+    PIO,
+};
+use crate::dma::{single_buffer, Byte, SingleChannel};
+use crate::gpio::{DynPinId, Pin, PinId, PullType};
+
+/// The largest delay a single PIO instruction can encode: each `OUT`/`SET`
+/// here carries its delay in the instruction's 5-bit delay field (see
+/// `pio_core::Instruction::encode`), with no side-set bits taken out of it.
+pub const MAX_DELAY_CYCLES: u8 = 31;
+
+/// Configuration for a [`ParallelOut`] bus.
+///
+/// All durations are expressed in PIO clock cycles, not CPU cycles or
+/// wall-clock time, since the enable strobe is generated entirely by the
+/// state machine. The state machine runs at the default clock divisor of 1,
+/// so one PIO clock cycle is one `clk_sys` cycle. Each duration is encoded in
+/// a single instruction's delay field, on top of the one cycle the instruction
+/// itself takes, so it is capped at [`MAX_DELAY_CYCLES`] (31) cycles.
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelOutConfig {
+    setup_cycles: u8,
+    enable_pulse_cycles: u8,
+    hold_cycles: u8,
+}
+
+impl ParallelOutConfig {
+    /// Creates a config with a one-cycle setup/pulse/hold delay, suitable
+    /// as a starting point for [`Self::setup_cycles`], [`Self::enable_pulse_cycles`]
+    /// and [`Self::hold_cycles`] to be tuned for the target device.
+    pub fn new() -> Self {
+        Self {
+            setup_cycles: 1,
+            enable_pulse_cycles: 1,
+            hold_cycles: 1,
+        }
+    }
+
+    /// Number of PIO clock cycles to wait after the data pins are set, before the
+    /// enable pin is asserted. Gives the data pins time to settle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cycles` is greater than [`MAX_DELAY_CYCLES`] (31).
+    pub fn setup_cycles(mut self, cycles: u8) -> Self {
+        assert!(
+            cycles <= MAX_DELAY_CYCLES,
+            "setup_cycles of {cycles} exceeds the PIO delay field's limit of {MAX_DELAY_CYCLES}"
+        );
+        self.setup_cycles = cycles;
+        self
+    }
+
+    /// Number of PIO clock cycles the enable pin is held high, on top of the
+    /// one cycle of the `SET` instruction that raises it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cycles` is greater than [`MAX_DELAY_CYCLES`] (31).
+    pub fn enable_pulse_cycles(mut self, cycles: u8) -> Self {
+        assert!(
+            cycles <= MAX_DELAY_CYCLES,
+            "enable_pulse_cycles of {cycles} exceeds the PIO delay field's limit of {MAX_DELAY_CYCLES}"
+        );
+        self.enable_pulse_cycles = cycles;
+        self
+    }
+
+    /// Number of PIO clock cycles to wait after the enable pin is deasserted,
+    /// before the next word may be pulled from the Tx FIFO.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cycles` is greater than [`MAX_DELAY_CYCLES`] (31).
+    pub fn hold_cycles(mut self, cycles: u8) -> Self {
+        assert!(
+            cycles <= MAX_DELAY_CYCLES,
+            "hold_cycles of {cycles} exceeds the PIO delay field's limit of {MAX_DELAY_CYCLES}"
+        );
+        self.hold_cycles = cycles;
+        self
+    }
+}
+
+impl Default for ParallelOutConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A `WIDTH`-bit data bus plus enable strobe, driven by a PIO state machine.
+///
+/// `WIDTH` is 4 for a bus built with [`Self::new_4bit`] or 8 for one built
+/// with [`Self::new_8bit`]. Each word pushed to the Tx FIFO (via
+/// [`Self::write_word`] and the width-specific helpers built on it, or
+/// [`Self::write_all`]) is latched onto the data pins and accompanied by
+/// one enable pulse, with the timing configured through [`ParallelOutConfig`].
+pub struct ParallelOut<P: PIOExt, SM: StateMachineIndex, const WIDTH: usize> {
+    sm: StateMachine<(P, SM), crate::pio::Running>,
+    rx: Rx<(P, SM)>,
+    tx: Tx<(P, SM)>,
+    // Only held to keep the pins reserved for as long as the bus is alive.
+    _data_pins: [Pin<DynPinId, P::PinFunction, crate::gpio::PullNone>; WIDTH],
+    _enable_pin: Pin<DynPinId, P::PinFunction, crate::gpio::PullNone>,
+}
+
+impl<P: PIOExt, SM: StateMachineIndex, const WIDTH: usize> ParallelOut<P, SM, WIDTH>
+where
+    (P, SM): ValidStateMachine<PIO = P>,
+{
+    /// Reserves `data_pins` (which must be `WIDTH` consecutive GPIOs - a
+    /// requirement of the PIO `OUT` instruction, which addresses a
+    /// contiguous pin group) and `enable`, and builds+starts the PIO program
+    /// that drives them.
+    fn build(
+        pio: &mut PIO<P>,
+        sm: UninitStateMachine<(P, SM)>,
+        data_pins: [Pin<DynPinId, P::PinFunction, crate::gpio::PullNone>; WIDTH],
+        enable: Pin<DynPinId, P::PinFunction, crate::gpio::PullNone>,
+        config: ParallelOutConfig,
+    ) -> Self {
+        let data_base = data_pins[0].id().num;
+        for (offset, pin) in data_pins.iter().enumerate() {
+            assert_eq!(
+                pin.id().num,
+                data_base + offset as u8,
+                "data pins must be consecutive GPIOs"
+            );
+        }
+        let enable_pin = enable.id().num;
+
+        let program = Self::assemble(config);
+        let installed = pio.install(&program).unwrap();
+
+        let (mut sm, rx, tx) = crate::pio::PIOBuilder::from_installed_program(installed)
+            .out_pins(data_base, WIDTH as u8)
+            .set_pins(enable_pin, 1)
+            .out_shift_direction(crate::pio::ShiftDirection::Right)
+            .autopull(false)
+            .buffers(crate::pio::Buffers::OnlyTx)
+            .build(sm);
+        let data_pindirs = core::array::from_fn::<_, WIDTH, _>(|offset| {
+            (data_base + offset as u8, PinDir::Output)
+        });
+        sm.set_pindirs(
+            data_pindirs
+                .into_iter()
+                .chain(core::iter::once((enable_pin, PinDir::Output))),
+        );
+
+        Self {
+            sm: sm.start(),
+            rx,
+            tx,
+            _data_pins: data_pins,
+            _enable_pin: enable,
+        }
+    }
+
+    fn assemble(config: ParallelOutConfig) -> pio::Program<{ pio::RP2040_MAX_PROGRAM_SIZE }> {
+        let mut a = Assembler::<{ pio::RP2040_MAX_PROGRAM_SIZE }>::new();
+
+        let mut wrap_target = a.label();
+        let mut wrap_source = a.label();
+
+        a.bind(&mut wrap_target);
+        // Block until the next word is available, then latch it.
+        a.pull(false, true);
+        a.out_with_delay(OutDestination::PINS, WIDTH as u8, config.setup_cycles);
+        a.set_with_delay(SetDestination::PINS, 1, config.enable_pulse_cycles);
+        a.set_with_delay(SetDestination::PINS, 0, config.hold_cycles);
+        a.bind(&mut wrap_source);
+
+        a.assemble_with_wrap(wrap_source, wrap_target)
+    }
+
+    /// Writes a single `WIDTH`-bit word (the low `WIDTH` bits of `word`) to
+    /// the data pins, pulsing enable once. Blocks until there is room in the
+    /// Tx FIFO.
+    pub fn write_word(&mut self, word: u32) {
+        let mask = (1u32 << WIDTH) - 1;
+        while !self.tx.write(word & mask) {}
+    }
+
+    /// Writes a whole buffer of words without further CPU involvement, by
+    /// handing the Tx FIFO to a DMA channel.
+    ///
+    /// Each byte of `data` is transferred as one `WIDTH`-bit word write, read
+    /// from that byte's low `WIDTH` bits. On a 4-bit bus this means `data`
+    /// must already be nibble-packed one nibble per byte - e.g. to send the
+    /// byte `0xAB` the way [`Self::write_byte`] would (upper nibble first),
+    /// push `[0x0A, 0x0B]` rather than `[0xAB]`.
+    ///
+    /// Consumes the bus, returning the running [`single_buffer::Transfer`].
+    /// Call [`single_buffer::Transfer::wait`] on it to recover the DMA
+    /// channel and buffer once the transfer has completed.
+    pub fn write_all<CH: SingleChannel>(
+        self,
+        ch: CH,
+        data: &'static [u8],
+    ) -> single_buffer::Transfer<CH, &'static [u8], Tx<(P, SM), Byte>> {
+        let tx = self.tx.transfer_size(Byte {});
+        single_buffer::Config::new(ch, data, tx).start()
+    }
+
+    /// Stops the state machine and releases the PIO program and state machine.
+    ///
+    /// The reserved data/enable pins are dropped; they are not returned to the
+    /// caller.
+    pub fn free(self, pio: &mut PIO<P>) -> UninitStateMachine<(P, SM)> {
+        let (sm, installed) = self.sm.stop().uninit(self.rx, self.tx);
+        pio.uninstall(installed);
+        sm
+    }
+}
+
+impl<P: PIOExt, SM: StateMachineIndex> ParallelOut<P, SM, 4>
+where
+    (P, SM): ValidStateMachine<PIO = P>,
+{
+    /// Creates a new PIO-backed 4-bit parallel bus.
+    ///
+    /// `d4..d7` must be four consecutive GPIO pins (`d5 == d4 + 1`, and so on).
+    /// `enable` may be any other pin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d4..d7` are not consecutive GPIO numbers.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_4bit<D4, D5, D6, D7, EN, PU>(
+        pio: &mut PIO<P>,
+        sm: UninitStateMachine<(P, SM)>,
+        d4: Pin<D4, P::PinFunction, PU>,
+        d5: Pin<D5, P::PinFunction, PU>,
+        d6: Pin<D6, P::PinFunction, PU>,
+        d7: Pin<D7, P::PinFunction, PU>,
+        enable: Pin<EN, P::PinFunction, PU>,
+        config: ParallelOutConfig,
+    ) -> Self
+    where
+        D4: PinId,
+        D5: PinId,
+        D6: PinId,
+        D7: PinId,
+        EN: PinId,
+        PU: PullType,
+    {
+        let data_pins = [
+            d4.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d5.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d6.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d7.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+        ];
+        let enable = enable
+            .into_pull_type::<crate::gpio::PullNone>()
+            .into_dyn_pin();
+        Self::build(pio, sm, data_pins, enable, config)
+    }
+
+    /// Writes a single nibble (the low 4 bits of `nibble`) to the data pins,
+    /// pulsing enable once. Blocks until there is room in the Tx FIFO.
+    pub fn write_nibble(&mut self, nibble: u8) {
+        self.write_word(nibble as u32);
+    }
+
+    /// Writes a byte as two nibbles (upper nibble first, then lower), the
+    /// convention used by HD44780-style 4-bit buses.
+    pub fn write_byte(&mut self, byte: u8) {
+        self.write_nibble(byte >> 4);
+        self.write_nibble(byte);
+    }
+}
+
+impl<P: PIOExt, SM: StateMachineIndex> ParallelOut<P, SM, 8>
+where
+    (P, SM): ValidStateMachine<PIO = P>,
+{
+    /// Creates a new PIO-backed 8-bit parallel bus.
+    ///
+    /// `d0..d7` must be eight consecutive GPIO pins (`d1 == d0 + 1`, and so on).
+    /// `enable` may be any other pin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `d0..d7` are not consecutive GPIO numbers.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_8bit<D0, D1, D2, D3, D4, D5, D6, D7, EN, PU>(
+        pio: &mut PIO<P>,
+        sm: UninitStateMachine<(P, SM)>,
+        d0: Pin<D0, P::PinFunction, PU>,
+        d1: Pin<D1, P::PinFunction, PU>,
+        d2: Pin<D2, P::PinFunction, PU>,
+        d3: Pin<D3, P::PinFunction, PU>,
+        d4: Pin<D4, P::PinFunction, PU>,
+        d5: Pin<D5, P::PinFunction, PU>,
+        d6: Pin<D6, P::PinFunction, PU>,
+        d7: Pin<D7, P::PinFunction, PU>,
+        enable: Pin<EN, P::PinFunction, PU>,
+        config: ParallelOutConfig,
+    ) -> Self
+    where
+        D0: PinId,
+        D1: PinId,
+        D2: PinId,
+        D3: PinId,
+        D4: PinId,
+        D5: PinId,
+        D6: PinId,
+        D7: PinId,
+        EN: PinId,
+        PU: PullType,
+    {
+        let data_pins = [
+            d0.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d1.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d2.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d3.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d4.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d5.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d6.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+            d7.into_pull_type::<crate::gpio::PullNone>().into_dyn_pin(),
+        ];
+        let enable = enable
+            .into_pull_type::<crate::gpio::PullNone>()
+            .into_dyn_pin();
+        Self::build(pio, sm, data_pins, enable, config)
+    }
+
+    /// Writes a whole byte to the data pins in a single transaction, pulsing
+    /// enable once.
+    pub fn write_byte(&mut self, byte: u8) {
+        self.write_word(byte as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParallelOut, ParallelOutConfig, MAX_DELAY_CYCLES};
+
+    #[test]
+    fn assemble_accepts_the_maximum_delay() {
+        // Exercises the real PIO assembler/encoder, not just the config struct:
+        // a delay of exactly MAX_DELAY_CYCLES must fit the instruction's delay field.
+        let config = ParallelOutConfig::new()
+            .setup_cycles(MAX_DELAY_CYCLES)
+            .enable_pulse_cycles(MAX_DELAY_CYCLES)
+            .hold_cycles(MAX_DELAY_CYCLES);
+        let _program = ParallelOut::<crate::pac::PIO0, crate::pio::SM0, 4>::assemble(config);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the PIO delay field's limit")]
+    fn config_rejects_a_delay_one_over_the_limit() {
+        ParallelOutConfig::new().setup_cycles(MAX_DELAY_CYCLES + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the PIO delay field's limit")]
+    fn config_rejects_an_out_of_range_enable_pulse() {
+        ParallelOutConfig::new().enable_pulse_cycles(MAX_DELAY_CYCLES + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the PIO delay field's limit")]
+    fn config_rejects_an_out_of_range_hold() {
+        ParallelOutConfig::new().hold_cycles(MAX_DELAY_CYCLES + 1);
+    }
+}